@@ -1,21 +1,26 @@
-//! This program is designed to measure the per-task overhead of the tokio runtime
-//! for CPU bound tasks
+//! This program measures the per-task overhead of the tokio runtime for CPU
+//! bound tasks, using `eq_dyn` over two `Int64Array`s as the unit of work
+//! ("rows per second" is how fast two arrays of integers can be compared).
 //!
-//! It does so by processing 100
+//! Rather than timing a single fixed batch size, it sweeps a configurable
+//! vector of batch sizes for each execution mode and fits an ordinary
+//! least-squares line `time_per_batch = a + b*batch_size`:
 //!
-//! Rows per second means "how fast can two arrays of integers be compared
+//! * the slope `b` is the marginal per-row cost, and
+//! * the intercept `a` is the fixed spawn/scheduling overhead paid regardless
+//!   of batch size.
 //!
-//! Then we will chart
-//!
-//! x-axis: batch size
-//! y-axis: total rows/second
-//!
-//! The intercept will then give us some idea of
-//! how many rows/second
-//! compute 100x the intercept (so how many rows /
+//! Dividing 1e9 by the intercept (ns) turns that fixed overhead into a
+//! rows/second figure, and the summary reports 100x that value — the "100x
+//! intercept" the benchmark is after. Each `(mode, batch_size, rows_per_sec)`
+//! sample is emitted as CSV so the sweep can be charted directly (x-axis: batch
+//! size, y-axis: total rows/second).
 
 use std::{
-    sync::Arc,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
     time::{Duration, Instant},
 };
 
@@ -51,48 +56,501 @@ fn random_array(rng: &mut ChaCha20Rng, num_rows: u32) -> ArrayRef {
     Arc::new(array)
 }
 
+/// Scheduler-counter instrumentation, gated behind the `runtime-metrics` feature
+/// so it only compiles when the runtime is built with `--cfg tokio_unstable`
+/// (the `runtime.metrics()` steal/park counters are themselves unstable API).
+#[cfg(all(feature = "runtime-metrics", tokio_unstable))]
+mod runtime_metrics {
+    use tokio::runtime::Runtime;
+
+    /// Snapshot of the cumulative scheduler counters we attribute overhead to.
+    ///
+    /// The steal/park/local-schedule counters are exposed per worker thread, so
+    /// those fields are summed across all workers at capture time.
+    /// `num_remote_schedules` is a runtime-level counter (tasks scheduled from
+    /// off-runtime threads, e.g. every spawn from the `block_on` thread) and is
+    /// read once rather than summed.
+    #[derive(Default)]
+    pub struct MetricsSnapshot {
+        total_steal_count: u64,
+        total_steal_operations: u64,
+        num_remote_schedules: u64,
+        total_park_count: u64,
+        total_local_schedule_count: u64,
+    }
+
+    impl MetricsSnapshot {
+        /// Read the current counter values, summing the per-worker ones.
+        pub fn capture(rt: &Runtime) -> Self {
+            let m = rt.metrics();
+            let mut snap = Self {
+                num_remote_schedules: m.remote_schedule_count(),
+                ..Self::default()
+            };
+            for w in 0..m.num_workers() {
+                snap.total_steal_count += m.worker_steal_count(w);
+                snap.total_steal_operations += m.worker_steal_operations(w);
+                snap.total_park_count += m.worker_park_count(w);
+                snap.total_local_schedule_count += m.worker_local_schedule_count(w);
+            }
+            snap
+        }
+    }
+
+    /// Running total of the deltas observed across an entire batch-size sweep,
+    /// so a mode reports its scheduler activity once rather than once per batch.
+    #[derive(Clone, Default)]
+    pub struct MetricsAccumulator {
+        inner: std::sync::Arc<std::sync::Mutex<MetricsSnapshot>>,
+    }
+
+    impl MetricsAccumulator {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Fold the delta between `before` and `after` into the totals.
+        pub fn add(&self, before: &MetricsSnapshot, after: &MetricsSnapshot) {
+            let mut totals = self.inner.lock().unwrap();
+            totals.total_steal_count += after.total_steal_count - before.total_steal_count;
+            totals.total_steal_operations +=
+                after.total_steal_operations - before.total_steal_operations;
+            totals.num_remote_schedules += after.num_remote_schedules - before.num_remote_schedules;
+            totals.total_park_count += after.total_park_count - before.total_park_count;
+            totals.total_local_schedule_count +=
+                after.total_local_schedule_count - before.total_local_schedule_count;
+        }
+
+        /// Print the accumulated deltas for `mode`.
+        pub fn report(&self, mode: &str) {
+            let totals = self.inner.lock().unwrap();
+            println!(
+                "# {} runtime metrics: steal_count={}, steal_operations={}, \
+                 remote_schedules={}, park_count={}, local_schedules={}",
+                mode,
+                totals.total_steal_count,
+                totals.total_steal_operations,
+                totals.num_remote_schedules,
+                totals.total_park_count,
+                totals.total_local_schedule_count,
+            );
+        }
+    }
+}
+
+/// default smoothing factor for the poll-duration EWMA
+const EWMA_ALPHA: f64 = 0.1;
+
+/// number of power-of-two nanosecond histogram buckets (the last one starts at
+/// 2^31 ns, ~2.1s, and also takes every longer poll)
+const NUM_POLL_BUCKETS: usize = 32;
+
+/// Accumulates an EWMA and a power-of-two bucketed histogram of poll durations.
+struct PollHistogram {
+    alpha: f64,
+    /// exponentially-weighted mean poll duration in nanoseconds
+    mean_poll_duration: f64,
+    /// whether at least one sample has seeded the EWMA
+    seeded: bool,
+    /// bucket `i` counts polls whose duration falls in `[2^i, 2^(i+1))` ns;
+    /// bucket 0 also counts 0 ns polls and the last bucket everything longer
+    buckets: [u64; NUM_POLL_BUCKETS],
+}
+
+impl PollHistogram {
+    fn new(alpha: f64) -> Self {
+        Self {
+            alpha,
+            mean_poll_duration: 0.0,
+            seeded: false,
+            buckets: [0; NUM_POLL_BUCKETS],
+        }
+    }
+
+    /// Fold a single poll duration into the EWMA and the histogram.
+    fn record(&mut self, sample: Duration) {
+        let ns = sample.as_nanos() as f64;
+        if self.seeded {
+            self.mean_poll_duration = self.alpha * ns + (1.0 - self.alpha) * self.mean_poll_duration;
+        } else {
+            self.mean_poll_duration = ns;
+            self.seeded = true;
+        }
+
+        let ns = sample.as_nanos();
+        let bucket = if ns == 0 {
+            0
+        } else {
+            // floor(log2(ns)), clamped to the last bucket
+            (127 - (ns.leading_zeros() as usize)).min(NUM_POLL_BUCKETS - 1)
+        };
+        self.buckets[bucket] += 1;
+    }
+}
+
+/// Cheap-to-clone handle over a shared [`PollHistogram`], stored alongside the
+/// runtime so any mode can instrument the futures it spawns without reaching
+/// into `do_async_work`.
+#[derive(Clone)]
+struct PollInstrument {
+    inner: Arc<Mutex<PollHistogram>>,
+}
+
+impl PollInstrument {
+    fn new(alpha: f64) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(PollHistogram::new(alpha))),
+        }
+    }
+
+    /// Wrap `fut` so each of its polls is timed and recorded.
+    fn instrument<F>(&self, fut: F) -> Instrumented<F> {
+        Instrumented {
+            inner: fut,
+            instrument: self.clone(),
+        }
+    }
+
+    fn record(&self, sample: Duration) {
+        self.inner.lock().unwrap().record(sample);
+    }
+
+    /// Print the EWMA mean poll duration and the non-empty histogram buckets.
+    fn report(&self, mode: &str) {
+        let h = self.inner.lock().unwrap();
+        println!(
+            "# {} poll EWMA mean = {:.1} ns",
+            mode, h.mean_poll_duration
+        );
+        for (i, &count) in h.buckets.iter().enumerate() {
+            if count > 0 {
+                let lo = 1u128 << i;
+                println!("# {} poll bucket [{}..{}) ns: {}", mode, lo, lo << 1, count);
+            }
+        }
+    }
+}
+
+/// Future wrapper produced by [`PollInstrument::instrument`] that times every
+/// `poll` of the inner future and feeds the result back to the shared instrument.
+struct Instrumented<F> {
+    inner: F,
+    instrument: PollInstrument,
+}
+
+impl<F: Future> Future for Instrumented<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: we never move `inner` out of the pinned wrapper, and
+        // `instrument` is `Unpin`, so projecting the pin to `inner` is sound.
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
+
+        let start = Instant::now();
+        let res = inner.poll(cx);
+        this.instrument.record(start.elapsed());
+        res
+    }
+}
+
 const RNG_SEED: u64 = 42;
 
 const NUM_RUNS: u32 = 10_000_000;
 const NUM_ROWS: u32 = 100;
 const NUM_PARALLEL: u32 = 8;
 
+/// batch sizes (rows per array) swept to recover the per-task overhead by
+/// regression; `NUM_ROWS` is kept in the sweep as a reference point
+const BATCH_SIZES: &[u32] = &[10, 25, 50, NUM_ROWS, 200, 400];
+
+/// Runtime shape and workload parameters, resolved from the command line.
+///
+/// Every field defaults to the matching `RNG_SEED`/`NUM_*`/`BATCH_SIZES`
+/// constant, except `worker_threads`, which defaults to 4.
+struct Config {
+    seed: u64,
+    num_runs: u32,
+    num_parallel: u32,
+    worker_threads: usize,
+    batch_sizes: Vec<u32>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            seed: RNG_SEED,
+            num_runs: NUM_RUNS,
+            num_parallel: NUM_PARALLEL,
+            worker_threads: 4,
+            batch_sizes: BATCH_SIZES.to_vec(),
+        }
+    }
+}
+
+impl Config {
+    /// Parse `--flag value` pairs from `std::env::args`, leaving unspecified
+    /// fields at their defaults. Unknown flags abort with a usage message, and
+    /// missing or bad values panic naming the flag, matching the crate's habit
+    /// of `unwrap`-ing on misuse.
+    fn from_args() -> Self {
+        let mut cfg = Config::default();
+        let mut args = std::env::args().skip(1);
+        while let Some(flag) = args.next() {
+            let mut value = || {
+                args.next()
+                    .unwrap_or_else(|| panic!("missing value for {flag}"))
+            };
+            match flag.as_str() {
+                "--seed" => cfg.seed = value().parse().expect("seed must be a u64"),
+                "--num-runs" => cfg.num_runs = value().parse().expect("num-runs must be a u32"),
+                "--parallel" => {
+                    cfg.num_parallel = value().parse().expect("parallel must be a u32")
+                }
+                "--worker-threads" => {
+                    cfg.worker_threads = value().parse().expect("worker-threads must be a usize")
+                }
+                "--batch-sizes" => {
+                    cfg.batch_sizes = value()
+                        .split(',')
+                        .map(|s| s.trim().parse().expect("batch sizes must be u32"))
+                        .collect();
+                    assert!(!cfg.batch_sizes.is_empty(), "--batch-sizes cannot be empty");
+                }
+                other => panic!(
+                    "unknown flag {other}; usage: [--seed N] [--num-runs N] \
+                     [--parallel N] [--worker-threads N] [--batch-sizes a,b,c]"
+                ),
+            }
+        }
+        cfg
+    }
+}
+
+/// Per-mode instruments shared across every batch of a sweep so their output
+/// is aggregated and reported once, after the batch loop, rather than per batch.
+struct RunContext {
+    poll: PollInstrument,
+    #[cfg(all(feature = "runtime-metrics", tokio_unstable))]
+    metrics: runtime_metrics::MetricsAccumulator,
+}
+
+impl RunContext {
+    fn new() -> Self {
+        Self {
+            poll: PollInstrument::new(EWMA_ALPHA),
+            #[cfg(all(feature = "runtime-metrics", tokio_unstable))]
+            metrics: runtime_metrics::MetricsAccumulator::new(),
+        }
+    }
+}
+
+/// the four execution strategies we sweep and fit independently
+#[derive(Clone, Copy)]
+enum Mode {
+    Sync,
+    AsyncCurrentThread,
+    AsyncMultiThread,
+    AsyncBlocking,
+}
+
+impl Mode {
+    /// label used in the CSV `mode` column and the summary lines
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Sync => "sync",
+            Mode::AsyncCurrentThread => "async_current_thread",
+            Mode::AsyncMultiThread => "async_multi_thread",
+            Mode::AsyncBlocking => "async_blocking",
+        }
+    }
+
+    /// run `cfg.num_runs` iterations over the given arrays and return the average
+    /// wall-clock time for a single run (which dispatches `cfg.num_parallel` batches)
+    fn run(self, a1: ArrayRef, a2: ArrayRef, cfg: &Config, ctx: &RunContext) -> Duration {
+        match self {
+            Mode::Sync => test(a1, a2, cfg),
+            Mode::AsyncCurrentThread => async_test(a1, a2, cfg, ctx),
+            Mode::AsyncMultiThread => async_test2(a1, a2, cfg, ctx),
+            Mode::AsyncBlocking => async_test_blocking(a1, a2, cfg),
+        }
+    }
+
+    /// Emit this mode's aggregated diagnostics once, after the whole sweep.
+    fn report(self, ctx: &RunContext) {
+        match self {
+            Mode::AsyncCurrentThread => {
+                // steal/park counters are always zero on the current-thread
+                // scheduler, so there is nothing to attribute here
+                #[cfg(all(feature = "runtime-metrics", tokio_unstable))]
+                println!("# {} runtime metrics: metrics unavailable", self.name());
+                ctx.poll.report(self.name());
+            }
+            Mode::AsyncMultiThread => {
+                #[cfg(all(feature = "runtime-metrics", tokio_unstable))]
+                ctx.metrics.report(self.name());
+                ctx.poll.report(self.name());
+            }
+            // the sync and blocking modes spawn no instrumented futures
+            Mode::Sync | Mode::AsyncBlocking => {}
+        }
+    }
+}
+
+/// result of fitting `time_per_batch = a + b*batch_size` by ordinary least squares
+struct LinearFit {
+    /// marginal per-row cost `b`
+    slope: f64,
+    /// fixed spawn/scheduling overhead `a`; `None` on a degenerate sweep
+    intercept: Option<f64>,
+    /// coefficient of determination; `None` when it cannot be computed
+    r_squared: Option<f64>,
+}
+
+/// Fit `y = a + b*x` to `(x, y)` samples via ordinary least squares.
+///
+/// A single distinct `x` makes `Σ(xᵢ-x̄)² == 0`, so we cannot solve for an
+/// intercept and report slope-only instead (reusing the mean `ȳ` as slope).
+fn ordinary_least_squares(samples: &[(f64, f64)]) -> LinearFit {
+    let n = samples.len() as f64;
+    let x_mean = samples.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let y_mean = samples.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let s_xx: f64 = samples.iter().map(|(x, _)| (x - x_mean).powi(2)).sum();
+    let s_xy: f64 = samples
+        .iter()
+        .map(|(x, y)| (x - x_mean) * (y - y_mean))
+        .sum();
+
+    if s_xx == 0.0 {
+        return LinearFit {
+            slope: y_mean,
+            intercept: None,
+            r_squared: None,
+        };
+    }
+
+    let slope = s_xy / s_xx;
+    let intercept = y_mean - slope * x_mean;
+
+    let ss_tot: f64 = samples.iter().map(|(_, y)| (y - y_mean).powi(2)).sum();
+    let ss_res: f64 = samples
+        .iter()
+        .map(|(x, y)| {
+            let predicted = intercept + slope * x;
+            (y - predicted).powi(2)
+        })
+        .sum();
+    let r_squared = (ss_tot != 0.0).then(|| 1.0 - ss_res / ss_tot);
+
+    LinearFit {
+        slope,
+        intercept: Some(intercept),
+        r_squared,
+    }
+}
+
 fn main() {
-    let mut rng = ChaCha20Rng::seed_from_u64(RNG_SEED);
+    let cfg = Config::from_args();
+    let mut rng = ChaCha20Rng::seed_from_u64(cfg.seed);
+
+    // the largest sweep point sizes the arrays; every smaller batch slices a
+    // prefix so all modes compare the same deterministic data
+    let max_rows = cfg.batch_sizes.iter().copied().max().unwrap_or(NUM_ROWS);
+    eprintln!("Setting up a1...");
+    let a1 = random_array(&mut rng, max_rows);
+    eprintln!("Setting up a2...");
+    let a2 = random_array(&mut rng, max_rows);
+
+    // CSV header for the chart data the program promises
+    println!("mode,batch_size,rows_per_sec");
+
+    for mode in [
+        Mode::Sync,
+        Mode::AsyncCurrentThread,
+        Mode::AsyncMultiThread,
+        Mode::AsyncBlocking,
+    ] {
+        // instruments are shared across the sweep so they report once per mode
+        let ctx = RunContext::new();
+
+        // (x = batch_size, y = elapsed_ns per single batch) for the regression
+        let mut samples: Vec<(f64, f64)> = Vec::with_capacity(cfg.batch_sizes.len());
+        for &batch_size in &cfg.batch_sizes {
+            let left = a1.slice(0, batch_size as usize);
+            let right = a2.slice(0, batch_size as usize);
+
+            let time_per_run = mode.run(left, right, &cfg, &ctx);
+            // each run dispatches cfg.num_parallel batches
+            let time_per_batch = time_per_run / cfg.num_parallel;
+            let secs = time_per_batch.as_secs_f64();
+            let rows_per_sec = if secs > 0.0 {
+                batch_size as f64 / secs
+            } else {
+                f64::INFINITY
+            };
 
-    // create an array of num_ros
-    println!("Setting up a1...");
-    let a1 = random_array(&mut rng, NUM_ROWS);
-    println!("Setting up a2...");
-    let a2 = random_array(&mut rng, NUM_ROWS);
+            println!("{},{},{:.3}", mode.name(), batch_size, rows_per_sec);
+            samples.push((batch_size as f64, time_per_batch.as_nanos() as f64));
+        }
 
-    test(a1.clone(), a2.clone());
-    async_test(a1.clone(), a2.clone());
+        let fit = ordinary_least_squares(&samples);
+        match fit.intercept {
+            Some(intercept) => {
+                // intercept is the fixed per-batch overhead in ns; the header
+                // asks for "100x the intercept" expressed as rows/second
+                let intercept_rows_per_sec = if intercept > 0.0 {
+                    1e9 / intercept
+                } else {
+                    f64::INFINITY
+                };
+                println!(
+                    "# {}: slope={:.3} ns/row, intercept={:.3} ns, R²={}, \
+                     100x-intercept={:.1} rows/sec",
+                    mode.name(),
+                    fit.slope,
+                    intercept,
+                    fit.r_squared
+                        .map(|r| format!("{:.4}", r))
+                        .unwrap_or_else(|| "n/a".to_string()),
+                    100.0 * intercept_rows_per_sec,
+                );
+            }
+            None => {
+                println!(
+                    "# {}: degenerate sweep (single batch size), \
+                     slope-only mean={:.3} ns/batch",
+                    mode.name(),
+                    fit.slope,
+                );
+            }
+        }
 
-    async_test2(a1.clone(), a2.clone());
+        // aggregated instrument/metrics output, one report per mode
+        mode.report(&ctx);
+    }
 }
 
-fn test(a1: ArrayRef, a2: ArrayRef) {
-    println!("Begin non async...");
-    let total_time: Duration = (0..NUM_RUNS)
+/// Run the synchronous baseline and return the average time for a single run.
+fn test(a1: ArrayRef, a2: ArrayRef, cfg: &Config) -> Duration {
+    eprintln!("Begin non async...");
+    let total_time: Duration = (0..cfg.num_runs)
         .map(|_| {
             let start = Instant::now();
             // sequentially
-            for _ in 0..NUM_PARALLEL {
+            for _ in 0..cfg.num_parallel {
                 do_work(a1.clone(), a2.clone());
             }
             start.elapsed()
         })
         .sum();
 
-    println!("ran {} runs in {:?}", NUM_RUNS, total_time);
-    let time_per_run = total_time / NUM_RUNS;
-
-    println!("average time per run: {:?}", time_per_run);
+    total_time / cfg.num_runs
 }
 
-fn async_test(a1: ArrayRef, a2: ArrayRef) {
-    println!("Begin async...");
+/// Run the current-thread tokio flavor and return the average time per run.
+fn async_test(a1: ArrayRef, a2: ArrayRef, cfg: &Config, ctx: &RunContext) -> Duration {
+    eprintln!("Begin async...");
 
     // now run with tokio
     let builder = tokio::runtime::Builder::new_current_thread()
@@ -100,14 +558,19 @@ fn async_test(a1: ArrayRef, a2: ArrayRef) {
         .unwrap();
 
     let builder = &builder;
+    let (num_runs, num_parallel) = (cfg.num_runs, cfg.num_parallel);
+    let instrument = &ctx.poll;
     builder.block_on(async move {
         let mut total_time: Duration = Default::default();
-        for _ in 0..NUM_RUNS {
+        for _ in 0..num_runs {
             let start = Instant::now();
 
-            let _res: () = (0..NUM_PARALLEL)
-                .map(|_| async {
-                    let _ = builder.spawn(do_async_work(a1.clone(), a2.clone())).await;
+            let _res: () = (0..num_parallel)
+                .map(|_| {
+                    let fut = instrument.instrument(do_async_work(a1.clone(), a2.clone()));
+                    async move {
+                        let _ = builder.spawn(fut).await;
+                    }
                 })
                 .collect::<FuturesUnordered<_>>()
                 .collect()
@@ -116,31 +579,83 @@ fn async_test(a1: ArrayRef, a2: ArrayRef) {
             total_time += start.elapsed()
         }
 
-        println!("ran {} runs in {:?}", NUM_RUNS, total_time);
-        let time_per_run = total_time / NUM_RUNS;
+        total_time / num_runs
+    })
+}
+
+/// Run the multi-thread tokio flavor and return the average time per run.
+fn async_test2(a1: ArrayRef, a2: ArrayRef, cfg: &Config, ctx: &RunContext) -> Duration {
+    eprintln!("Begin async with multi-threads...");
+
+    // now run with tokio
+    let builder = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(cfg.worker_threads)
+        .build()
+        .unwrap();
+
+    let builder = &builder;
+    #[cfg(all(feature = "runtime-metrics", tokio_unstable))]
+    let before = runtime_metrics::MetricsSnapshot::capture(builder);
+
+    let (num_runs, num_parallel) = (cfg.num_runs, cfg.num_parallel);
+    let instrument = &ctx.poll;
+    let time_per_run = builder.block_on(async move {
+        let mut total_time: Duration = Default::default();
+        for _ in 0..num_runs {
+            let start = Instant::now();
+
+            let _res: () = (0..num_parallel)
+                .map(|_| {
+                    let fut = instrument.instrument(do_async_work(a1.clone(), a2.clone()));
+                    async move {
+                        let _ = builder.spawn(fut).await;
+                    }
+                })
+                .collect::<FuturesUnordered<_>>()
+                .collect()
+                .await;
+            total_time += start.elapsed()
+        }
 
-        println!("average time per run: {:?}", time_per_run);
+        total_time / num_runs
     });
+
+    // fold this batch's scheduler delta into the sweep-wide accumulator
+    #[cfg(all(feature = "runtime-metrics", tokio_unstable))]
+    ctx.metrics
+        .add(&before, &runtime_metrics::MetricsSnapshot::capture(builder));
+
+    time_per_run
 }
 
-fn async_test2(a1: ArrayRef, a2: ArrayRef) {
-    println!("Begin async with multi-threads...");
+/// Run the blocking-pool flavor and return the average time per run.
+///
+/// Mirrors `async_test2` but dispatches each `do_work` call onto the dedicated
+/// blocking thread pool via `spawn_blocking` instead of `spawn`-ing an async
+/// task onto the worker threads.
+fn async_test_blocking(a1: ArrayRef, a2: ArrayRef, cfg: &Config) -> Duration {
+    eprintln!("Begin async with spawn_blocking...");
 
     // now run with tokio
     let builder = tokio::runtime::Builder::new_multi_thread()
-        .worker_threads(4)
+        .worker_threads(cfg.worker_threads)
         .build()
         .unwrap();
 
     let builder = &builder;
+    let (num_runs, num_parallel) = (cfg.num_runs, cfg.num_parallel);
     builder.block_on(async move {
         let mut total_time: Duration = Default::default();
-        for _ in 0..NUM_RUNS {
+        for _ in 0..num_runs {
             let start = Instant::now();
 
-            let _res: () = (0..NUM_PARALLEL)
-                .map(|_| async {
-                    let _ = builder.spawn(do_async_work(a1.clone(), a2.clone())).await;
+            let _res: () = (0..num_parallel)
+                .map(|_| {
+                    let a1 = a1.clone();
+                    let a2 = a2.clone();
+                    async move {
+                        let _ = builder.spawn_blocking(move || do_work(a1, a2)).await;
+                    }
                 })
                 .collect::<FuturesUnordered<_>>()
                 .collect()
@@ -148,9 +663,6 @@ fn async_test2(a1: ArrayRef, a2: ArrayRef) {
             total_time += start.elapsed()
         }
 
-        println!("ran {} runs in {:?}", NUM_RUNS, total_time);
-        let time_per_run = total_time / NUM_RUNS;
-
-        println!("average time per run: {:?}", time_per_run);
-    });
+        total_time / num_runs
+    })
 }